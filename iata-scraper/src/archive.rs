@@ -0,0 +1,109 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+/// One entry in the archive's `manifest.json`, recording enough to
+/// reproduce or audit the run: which logo came from where and how big it was.
+#[derive(Serialize)]
+struct ManifestEntry {
+    iata: String,
+    source_url: String,
+    filename: String,
+    bytes: u64,
+}
+
+/// Bundle every downloaded logo plus the scraped table (`table_path`, in
+/// whichever output format was chosen) into a single archive at
+/// `archive_path`. The extension (`.zip`, or `.tar.gz`/`.tgz`) selects the
+/// format. A `manifest.json` entry inside records each IATA code, source
+/// logo URL, saved filename, and byte size for reproducibility.
+pub fn build_archive(archive_path: &Path, table_path: &str, out_dir: &str, base_logo_url: &str) -> Result<()> {
+    let logos = collect_logos(out_dir, base_logo_url)?;
+    let manifest = serde_json::to_vec_pretty(&logos)?;
+    let name = archive_path.to_string_lossy();
+
+    if name.ends_with(".zip") {
+        write_zip(archive_path, table_path, out_dir, &logos, &manifest)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        write_tar_gz(archive_path, table_path, out_dir, &logos, &manifest)
+    } else {
+        anyhow::bail!("unsupported archive extension (expected .zip or .tar.gz): {}", archive_path.display())
+    }
+}
+
+fn collect_logos(out_dir: &str, base_logo_url: &str) -> Result<Vec<ManifestEntry>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(out_dir).with_context(|| format!("reading {out_dir}"))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "meta") {
+            continue;
+        }
+        let (Some(iata), Some(filename)) = (
+            path.file_stem().and_then(|s| s.to_str()),
+            path.file_name().and_then(|s| s.to_str()),
+        ) else {
+            continue;
+        };
+        let bytes = fs::metadata(&path)?.len();
+        entries.push(ManifestEntry {
+            source_url: format!("{base_logo_url}{iata}"),
+            iata: iata.to_string(),
+            filename: filename.to_string(),
+            bytes,
+        });
+    }
+    entries.sort_by(|a, b| a.iata.cmp(&b.iata));
+    Ok(entries)
+}
+
+fn write_zip(archive_path: &Path, table_path: &str, out_dir: &str, logos: &[ManifestEntry], manifest: &[u8]) -> Result<()> {
+    let file = File::create(archive_path).with_context(|| format!("creating {}", archive_path.display()))?;
+    let mut zip = zip::ZipWriter::new(BufWriter::new(file));
+    let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let table_name = Path::new(table_path).file_name().context("table_path has no filename")?.to_string_lossy().into_owned();
+    zip.start_file(table_name, options)?;
+    zip.write_all(&fs::read(table_path)?)?;
+
+    for entry in logos {
+        zip.start_file(format!("logos/{}", entry.filename), options)?;
+        zip.write_all(&fs::read(Path::new(out_dir).join(&entry.filename))?)?;
+    }
+
+    zip.start_file("manifest.json", options)?;
+    zip.write_all(manifest)?;
+
+    zip.finish()?;
+    Ok(())
+}
+
+fn write_tar_gz(
+    archive_path: &Path,
+    table_path: &str,
+    out_dir: &str,
+    logos: &[ManifestEntry],
+    manifest: &[u8],
+) -> Result<()> {
+    let file = File::create(archive_path).with_context(|| format!("creating {}", archive_path.display()))?;
+    let enc = flate2::write::GzEncoder::new(BufWriter::new(file), flate2::Compression::default());
+    let mut tar = tar::Builder::new(enc);
+
+    let table_name = Path::new(table_path).file_name().context("table_path has no filename")?;
+    tar.append_path_with_name(table_path, table_name)?;
+
+    for entry in logos {
+        tar.append_path_with_name(Path::new(out_dir).join(&entry.filename), format!("logos/{}", entry.filename))?;
+    }
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, "manifest.json", manifest)?;
+
+    tar.into_inner()?.finish()?;
+    Ok(())
+}
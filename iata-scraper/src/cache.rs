@@ -0,0 +1,38 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Sidecar cache metadata for a downloaded logo, written alongside the file
+/// (e.g. `AB.png` -> `AB.png.meta`) so the next run can send conditional
+/// request validators instead of re-fetching unchanged files.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CacheMeta {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheMeta {
+    pub(crate) fn sidecar_path(dst: &Path) -> PathBuf {
+        let mut name: OsString = dst.as_os_str().to_owned();
+        name.push(".meta");
+        PathBuf::from(name)
+    }
+
+    /// Load the sidecar for `dst`, if both the file and its metadata exist.
+    pub fn load(dst: &Path) -> Option<CacheMeta> {
+        if !dst.exists() {
+            return None;
+        }
+        let data = fs::read(Self::sidecar_path(dst)).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    pub fn save(&self, dst: &Path) -> Result<()> {
+        let data = serde_json::to_vec_pretty(self)?;
+        fs::write(Self::sidecar_path(dst), data)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+/// Map a `Content-Type` header value to the file extension we save logos
+/// under.
+fn extension_from_content_type(content_type: &str) -> Option<&'static str> {
+    let ct = content_type.split(';').next().unwrap_or(content_type).trim();
+    match ct {
+        "image/svg+xml" => Some("svg"),
+        "image/png" => Some("png"),
+        "image/webp" => Some("webp"),
+        "image/jpeg" => Some("jpg"),
+        _ => None,
+    }
+}
+
+/// Sniff the leading magic bytes of image content when the `Content-Type`
+/// header is missing or unrecognized.
+fn extension_from_magic_bytes(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG") {
+        Some("png")
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("webp")
+    } else if bytes.starts_with(b"<svg") || bytes.starts_with(b"<?xml") {
+        Some("svg")
+    } else if bytes.starts_with(b"\xFF\xD8\xFF") {
+        Some("jpg")
+    } else {
+        None
+    }
+}
+
+/// Determine the on-disk extension for downloaded logo bytes: prefer the
+/// `Content-Type` header, fall back to sniffing magic bytes, and default to
+/// `png` when neither is conclusive.
+pub fn detect_extension(content_type: Option<&str>, bytes: &[u8]) -> &'static str {
+    content_type
+        .and_then(extension_from_content_type)
+        .or_else(|| extension_from_magic_bytes(bytes))
+        .unwrap_or("png")
+}
@@ -1,35 +1,143 @@
+mod archive;
+mod cache;
+mod format;
+mod rate_limit;
+mod retry;
+mod serve;
+mod sink;
+
 use anyhow::{Context, Result};
-use csv::{ReaderBuilder, WriterBuilder};
+use clap::{Args, Parser, Subcommand};
+use colored::Colorize;
 use futures::{stream, StreamExt};
+use reqwest::header::{CONTENT_TYPE, ETAG, LAST_MODIFIED};
 use reqwest::Client;
 use scraper::{Html, Selector};
 use std::collections::HashSet;
 use std::fs;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use cache::CacheMeta;
+use format::detect_extension;
+use rate_limit::RateLimiter;
+use retry::{fetch_with_retry, fetch_with_retry_conditional, FetchOutcome, RetryConfig};
+use sink::OutputFormat;
 
 const BASE_WIKI: &str = "https://en.wikipedia.org/wiki/List_of_airline_codes_";
 const CSV_PATH: &str = "airline_codes_all.csv";
 const OUT_DIR: &str = "airline_bitmaps";
 const UA: &str = "Mozilla/5.0 (compatible; iata-scraper/0.3; rust)";
 
+#[derive(Parser)]
+#[command(name = "iata-scraper")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Scrape IATA airline codes from Wikipedia and download each airline's logo.
+    Scrape(ScrapeArgs),
+    /// Browse a previous scrape's CSV and logos over a local HTTP directory index.
+    Serve(ServeArgs),
+}
+
+#[derive(Args)]
+struct ScrapeArgs {
+    /// Base URL under which per-IATA-code logo files are hosted, e.g. https://cdn.example.com/logos/
+    base_logo_url: String,
+
+    /// Maximum attempts per HTTP request before giving up.
+    #[arg(long, default_value_t = 5)]
+    max_retries: u32,
+
+    /// Initial backoff delay in seconds; doubles after each failure, capped at 30s.
+    #[arg(long, default_value_t = 1.0)]
+    base_delay: f64,
+
+    /// Maximum combined page fetches and logo downloads per minute (retries of a request are not counted).
+    #[arg(long, default_value_t = 120)]
+    rate: u32,
+
+    /// Maximum concurrent logo downloads.
+    #[arg(long, default_value_t = 12)]
+    concurrency: usize,
+
+    /// Bundle the run's logos and output table (in the chosen --format) into a single archive (.zip or .tar.gz) after scraping.
+    #[arg(long)]
+    archive: Option<PathBuf>,
+
+    /// Output backend for the scraped table.
+    #[arg(long, value_enum, default_value = "csv")]
+    format: OutputFormat,
+
+    /// Output path; defaults to airline_codes_all.<ext> for the chosen format.
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ServeArgs {
+    /// Address to bind the HTTP directory index to.
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    addr: SocketAddr,
+
+    /// Path to the scraped table to serve; must match the format it was written in.
+    #[arg(long, default_value = CSV_PATH)]
+    input: PathBuf,
+
+    /// Format of the table at `--input`.
+    #[arg(long, value_enum, default_value = "csv")]
+    format: OutputFormat,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let base_logo = std::env::args()
-        .nth(1)
-        .expect("usage: iata-scraper <base_logo_url/>\nexample: iata-scraper https://cdn.example.com/logos/");
-    let base_logo = ensure_trailing_slash(&base_logo);
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Scrape(args) => scrape(args).await,
+        Command::Serve(args) => serve::serve(args.addr, args.format, &args.input.to_string_lossy(), OUT_DIR).await,
+    }
+}
+
+async fn scrape(args: ScrapeArgs) -> Result<()> {
+    let base_logo = ensure_trailing_slash(&args.base_logo_url);
+    let retry_cfg = RetryConfig {
+        max_retries: args.max_retries,
+        base_delay: Duration::from_secs_f64(args.base_delay),
+    };
 
     let client = Client::builder().user_agent(UA).build().context("http client")?;
     fs::create_dir_all(OUT_DIR).context("mkdir output")?;
+    let limiter = RateLimiter::new(args.rate);
 
     // "0–9" plus A..Z
     let mut suffixes = vec!["0%E2%80%939".to_string()];
     suffixes.extend(('A'..='Z').map(|c| c.to_string()));
 
-    let (header, rows) = scrape_all(&client, &suffixes).await?;
-    write_csv_normalized(&header, &rows)?;
+    let (header, rows) = scrape_all(&client, &suffixes, &retry_cfg, &limiter).await?;
+
+    let output_path = args
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("airline_codes_all.{}", args.format.extension())));
+    let output_path_str = output_path.to_string_lossy().into_owned();
+    sink::write_rows(sink::create_sink(args.format, &output_path_str)?, &header, &rows)?;
+    println!("{} written: {output_path_str} ({} columns)", args.format.extension(), header.len());
+
+    let unique = unique_iata_codes(&header, &rows)?;
+    download_logos(&client, unique, OUT_DIR, &base_logo, &retry_cfg, &limiter, args.concurrency).await?;
+
+    if let Some(archive_path) = &args.archive {
+        archive::build_archive(archive_path, &output_path_str, OUT_DIR, &base_logo)?;
+        println!("Archive written: {}", archive_path.display());
+    }
 
-    download_logos(&client, CSV_PATH, OUT_DIR, &base_logo).await?;
     println!("Done.");
     Ok(())
 }
@@ -38,22 +146,35 @@ fn ensure_trailing_slash(s: &str) -> String {
     if s.ends_with('/') { s.to_string() } else { format!("{s}/") }
 }
 
-async fn scrape_all(client: &Client, suffixes: &[String]) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+async fn scrape_all(
+    client: &Client,
+    suffixes: &[String],
+    retry_cfg: &RetryConfig,
+    limiter: &RateLimiter,
+) -> Result<(Vec<String>, Vec<Vec<String>>)> {
     let mut header: Option<Vec<String>> = None;
     let mut rows_all: Vec<Vec<String>> = Vec::new();
+    let total = suffixes.len();
 
-    for s in suffixes {
+    for (n, s) in suffixes.iter().enumerate() {
+        limiter.acquire().await;
         let url = format!("{BASE_WIKI}({s})");
-        println!("Fetching: {url}");
-        match fetch_iata_table(client, &url).await {
-            Ok(Some((h, rows))) => {
-                if header.is_none() {
-                    header = Some(h);
-                }
-                rows_all.extend(rows);
+        let outcome = fetch_iata_table(client, &url, retry_cfg).await;
+
+        // Build the whole line before printing so it can't interleave with
+        // anything else writing to stdout.
+        let status = match &outcome {
+            Ok(Some((_, rows))) => format!("{} ({} rows)", "ok".green(), rows.len()),
+            Ok(None) => "no wikitable with IATA header".yellow().to_string(),
+            Err(e) => format!("{} {e}", "err".red()),
+        };
+        println!("[{}/{total}] fetching {url}... {status}", n + 1);
+
+        if let Ok(Some((h, rows))) = outcome {
+            if header.is_none() {
+                header = Some(h);
             }
-            Ok(None) => eprintln!("warn: {url}: no wikitable with IATA header"),
-            Err(e) => eprintln!("warn: {url}: {e}"),
+            rows_all.extend(rows);
         }
     }
 
@@ -62,15 +183,18 @@ async fn scrape_all(client: &Client, suffixes: &[String]) -> Result<(Vec<String>
 }
 
 /// Fetch the first wikitable whose header contains "IATA".
-async fn fetch_iata_table(client: &Client, url: &str) -> Result<Option<(Vec<String>, Vec<Vec<String>>)>> {
-    let body = client
-        .get(url)
-        .send()
-        .await?
-        .error_for_status()
-        .with_context(|| format!("GET {url}"))?
-        .text()
-        .await?;
+async fn fetch_iata_table(
+    client: &Client,
+    url: &str,
+    retry_cfg: &RetryConfig,
+) -> Result<Option<(Vec<String>, Vec<Vec<String>>)>> {
+    let body = match fetch_with_retry(client, url, retry_cfg).await? {
+        FetchOutcome::Response(resp) => resp.error_for_status().with_context(|| format!("GET {url}"))?.text().await?,
+        FetchOutcome::NotFound => return Ok(None),
+        // fetch_with_retry never sends conditional headers, so the server has
+        // no If-None-Match/If-Modified-Since to reply 304 against.
+        FetchOutcome::NotModified => unreachable!("fetch_with_retry never sends conditional headers"),
+    };
 
     let doc = Html::parse_document(&body);
 
@@ -112,95 +236,133 @@ fn extract_text(node: scraper::ElementRef<'_>) -> String {
     raw.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
-/// Normalize every row to the header width to avoid ragged CSV.
-fn write_csv_normalized(header: &[String], rows: &[Vec<String>]) -> Result<()> {
-    let mut wtr = WriterBuilder::new().has_headers(true).from_path(CSV_PATH)?;
-    let hlen = header.len();
-
-    wtr.write_record(header)?;
-    for r in rows {
-        if r.len() == hlen {
-            wtr.write_record(r)?;
-        } else if r.len() > hlen {
-            wtr.write_record(r.iter().take(hlen))?;
-        } else {
-            let mut tmp = Vec::with_capacity(hlen);
-            tmp.extend_from_slice(r);
-            tmp.resize(hlen, String::new());
-            wtr.write_record(&tmp)?;
-        }
-    }
-    wtr.flush()?;
-    println!("CSV written: {CSV_PATH} ({} columns)", hlen);
-    Ok(())
-}
-
-async fn download_logos(client: &Client, csv_path: &str, out_dir: &str, base_logo_url: &str) -> Result<()> {
-    let mut rdr = ReaderBuilder::new()
-        .has_headers(true)
-        .flexible(true)
-        .from_path(csv_path)?;
-
-    let headers = rdr.headers()?.clone();
-    let iata_index = headers
+/// Collect the distinct, well-formed 2-character IATA codes present in `rows`.
+fn unique_iata_codes(header: &[String], rows: &[Vec<String>]) -> Result<HashSet<String>> {
+    let iata_index = header
         .iter()
         .position(|h| h.trim().eq_ignore_ascii_case("IATA"))
         .context("IATA column not found")?;
 
-    let mut unique: HashSet<String> = HashSet::new();
-    for rec in rdr.records() {
-        let rec = rec?;
-        if let Some(val) = rec.get(iata_index) {
-            let code = val.trim().to_uppercase();
-            if code.len() == 2 && code.chars().all(|c| c.is_ascii_alphanumeric()) {
-                unique.insert(code);
-            }
-        }
-    }
+    Ok(rows
+        .iter()
+        .filter_map(|r| r.get(iata_index))
+        .map(|v| v.trim().to_uppercase())
+        .filter(|code| code.len() == 2 && code.chars().all(|c| c.is_ascii_alphanumeric()))
+        .collect())
+}
 
+async fn download_logos(
+    client: &Client,
+    unique: HashSet<String>,
+    out_dir: &str,
+    base_logo_url: &str,
+    retry_cfg: &RetryConfig,
+    limiter: &RateLimiter,
+    concurrency: usize,
+) -> Result<()> {
     fs::create_dir_all(out_dir).ok();
 
+    let total = unique.len();
+    let done = Arc::new(AtomicUsize::new(0));
+
     let tasks = unique.into_iter().map(|iata| {
         let out_dir = out_dir.to_string();
         let base = base_logo_url.to_string();
+        let done = done.clone();
         async move {
-            // Adjust extension if needed. Here: PNG.
-            let url = format!("{base}{iata}.png");
-            let path = Path::new(&out_dir).join(format!("{iata}.png"));
-
-            match try_download(client, &url, &path).await {
-                Ok(true) => {
-                    println!("ok   {iata}");
-                    Ok::<(), anyhow::Error>(())
-                }
-                Ok(false) => {
-                    println!("skip {iata} (not found)");
-                    Ok::<(), anyhow::Error>(())
-                }
-                Err(e) => {
-                    eprintln!("err  {iata}: {e}");
-                    Ok::<(), anyhow::Error>(())
-                }
-            }
+            let out_dir = Path::new(&out_dir);
+
+            limiter.acquire().await;
+            let outcome = try_download(client, &base, out_dir, &iata, retry_cfg).await;
+            let n = done.fetch_add(1, Ordering::SeqCst) + 1;
+
+            // Build the whole line before printing so concurrent tasks
+            // can't interleave their output mid-line.
+            let status = match &outcome {
+                Ok(DownloadOutcome::Downloaded) => "ok".green().to_string(),
+                Ok(DownloadOutcome::Unchanged) => "unchanged".yellow().to_string(),
+                Ok(DownloadOutcome::NotFound) => "skip (not found)".yellow().to_string(),
+                Err(e) => format!("{} {e}", "err".red()),
+            };
+            println!("[{n}/{total}] downloading {iata}... {status}");
         }
     });
 
-    stream::iter(tasks).buffer_unordered(12).collect::<Vec<_>>().await;
+    stream::iter(tasks).buffer_unordered(concurrency).collect::<Vec<_>>().await;
     Ok(())
 }
 
-async fn try_download(client: &Client, url: &str, dst: &PathBuf) -> Result<bool> {
-    let resp = client.get(url).send().await?;
-    let status = resp.status();
+/// Outcome of attempting to (re-)download a single logo.
+enum DownloadOutcome {
+    Downloaded,
+    Unchanged,
+    NotFound,
+}
 
-    if status.as_u16() == 404 || status.as_u16() == 410 {
-        return Ok(false); // skip
-    }
-    if !status.is_success() {
-        anyhow::bail!("http {}", status);
-    }
+/// Find a previously-downloaded logo for `iata` regardless of its
+/// extension (the CDN's format isn't known up front), so conditional
+/// re-download and extension changes both work against the same code.
+pub(crate) fn find_existing_logo(out_dir: &Path, iata: &str) -> Option<PathBuf> {
+    let entries = fs::read_dir(out_dir).ok()?;
+    entries.flatten().map(|e| e.path()).find(|path| {
+        path.extension().is_some_and(|ext| ext != "meta") && path.file_stem().and_then(|s| s.to_str()) == Some(iata)
+    })
+}
+
+async fn try_download(
+    client: &Client,
+    base_logo_url: &str,
+    out_dir: &Path,
+    iata: &str,
+    retry_cfg: &RetryConfig,
+) -> Result<DownloadOutcome> {
+    // Request the extensionless base URL; the CDN's Content-Type (or the
+    // bytes themselves) tell us what format it actually served.
+    let url = format!("{base_logo_url}{iata}");
+    let existing = find_existing_logo(out_dir, iata);
+    let cached = existing.as_deref().and_then(CacheMeta::load);
+
+    let resp = match &cached {
+        Some(meta) => {
+            match fetch_with_retry_conditional(
+                client,
+                &url,
+                retry_cfg,
+                meta.etag.as_deref(),
+                meta.last_modified.as_deref(),
+            )
+            .await?
+            {
+                FetchOutcome::Response(resp) => resp,
+                FetchOutcome::NotModified => return Ok(DownloadOutcome::Unchanged),
+                FetchOutcome::NotFound => return Ok(DownloadOutcome::NotFound),
+            }
+        }
+        None => match fetch_with_retry(client, &url, retry_cfg).await? {
+            FetchOutcome::Response(resp) => resp,
+            FetchOutcome::NotModified => return Ok(DownloadOutcome::Unchanged),
+            FetchOutcome::NotFound => return Ok(DownloadOutcome::NotFound),
+        },
+    };
+
+    let content_type = resp.headers().get(CONTENT_TYPE).and_then(|v| v.to_str().ok()).map(str::to_owned);
+    let new_meta = CacheMeta {
+        etag: resp.headers().get(ETAG).and_then(|v| v.to_str().ok()).map(String::from),
+        last_modified: resp.headers().get(LAST_MODIFIED).and_then(|v| v.to_str().ok()).map(String::from),
+    };
 
     let bytes = resp.bytes().await?;
-    fs::write(dst, &bytes)?;
-    Ok(true)
+    let ext = detect_extension(content_type.as_deref(), &bytes);
+    let dst = out_dir.join(format!("{iata}.{ext}"));
+
+    if let Some(old) = &existing {
+        if old != &dst {
+            let _ = fs::remove_file(old);
+            let _ = fs::remove_file(CacheMeta::sidecar_path(old));
+        }
+    }
+
+    fs::write(&dst, &bytes)?;
+    new_meta.save(&dst)?;
+    Ok(DownloadOutcome::Downloaded)
 }
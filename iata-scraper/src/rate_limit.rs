@@ -0,0 +1,37 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::time::{sleep_until, Instant};
+
+/// Interval-based rate gate shared by the sequential Wikipedia page loop and
+/// the concurrent logo fan-out: each page fetch and each logo download takes
+/// one permit, and permits are spaced `60 / requests_per_minute` seconds
+/// apart. Retries made inside a single fetch do not take further permits.
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_minute: u32) -> Arc<Self> {
+        let interval = Duration::from_secs_f64(60.0 / requests_per_minute.max(1) as f64);
+        Arc::new(Self {
+            interval,
+            next_slot: Mutex::new(Instant::now()),
+        })
+    }
+
+    /// Block until the next permit is available, reserving the following
+    /// slot for whoever calls next.
+    pub async fn acquire(&self) {
+        let wait_until = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let wait_until = (*next_slot).max(now);
+            *next_slot = wait_until + self.interval;
+            wait_until
+        };
+        sleep_until(wait_until).await;
+    }
+}
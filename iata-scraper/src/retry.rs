@@ -0,0 +1,126 @@
+use std::error::Error as _;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use rand::Rng;
+use reqwest::header::{HeaderName, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH};
+use reqwest::{Client, RequestBuilder, Response};
+
+/// Retry/backoff policy shared by every network call in the scraper.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            base_delay: Duration::from_secs_f64(1.0),
+        }
+    }
+}
+
+/// Result of a retried GET: a usable response, a definitive "not found"
+/// that the caller should treat as a skip, or a conditional-request
+/// "not modified" meaning the caller's cached copy is still good.
+pub enum FetchOutcome {
+    Response(Response),
+    NotFound,
+    NotModified,
+}
+
+/// GET `url` with exponential backoff and jitter.
+///
+/// - 404/410 return [`FetchOutcome::NotFound`] immediately, no retry.
+/// - Any other error or 5xx status sleeps then retries, up to
+///   `cfg.max_retries` attempts, doubling the delay each time (capped at
+///   30s) and jittering by 50-100% to avoid a thundering herd.
+/// - The dropped-stream `h2 protocol error: not a result of an error` is
+///   always retried regardless of the attempt budget, since it reflects a
+///   spurious connection reset rather than a real failure.
+pub async fn fetch_with_retry(client: &Client, url: &str, cfg: &RetryConfig) -> Result<FetchOutcome> {
+    send_with_retry(client, url, cfg, &[]).await
+}
+
+/// Like [`fetch_with_retry`], but attaches `If-None-Match`/`If-Modified-Since`
+/// validators and surfaces a `304 Not Modified` response as
+/// [`FetchOutcome::NotModified`] instead of an error.
+pub async fn fetch_with_retry_conditional(
+    client: &Client,
+    url: &str,
+    cfg: &RetryConfig,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> Result<FetchOutcome> {
+    let mut headers = Vec::new();
+    if let Some(etag) = etag {
+        headers.push((IF_NONE_MATCH, etag.to_string()));
+    }
+    if let Some(last_modified) = last_modified {
+        headers.push((IF_MODIFIED_SINCE, last_modified.to_string()));
+    }
+    send_with_retry(client, url, cfg, &headers).await
+}
+
+async fn send_with_retry(
+    client: &Client,
+    url: &str,
+    cfg: &RetryConfig,
+    headers: &[(HeaderName, String)],
+) -> Result<FetchOutcome> {
+    let mut delay = cfg.base_delay;
+    let mut attempt = 0u32;
+
+    loop {
+        attempt += 1;
+        let mut req: RequestBuilder = client.get(url);
+        for (name, value) in headers {
+            req = req.header(name.clone(), HeaderValue::from_str(value).context("invalid header value")?);
+        }
+
+        match req.send().await {
+            Ok(resp) => {
+                let status = resp.status();
+                if status.as_u16() == 304 {
+                    return Ok(FetchOutcome::NotModified);
+                }
+                if status.as_u16() == 404 || status.as_u16() == 410 {
+                    return Ok(FetchOutcome::NotFound);
+                }
+                if status.is_success() {
+                    return Ok(FetchOutcome::Response(resp));
+                }
+                if attempt >= cfg.max_retries {
+                    anyhow::bail!("GET {url}: http {status} after {attempt} attempts");
+                }
+            }
+            Err(e) => {
+                if attempt >= cfg.max_retries && !is_spurious_h2_error(&e) {
+                    return Err(e).with_context(|| format!("GET {url} after {attempt} attempts"));
+                }
+            }
+        }
+
+        sleep_with_jitter(delay).await;
+        delay = (delay * 2).min(Duration::from_secs(30));
+    }
+}
+
+/// Detect the "h2 protocol error: not a result of an error" case by
+/// walking the error chain: `reqwest::Error` -> `hyper::Error` -> `h2::Error`.
+/// This shows up as a dropped stream that carries no real failure, so it's
+/// worth retrying even past the normal attempt budget.
+fn is_spurious_h2_error(err: &reqwest::Error) -> bool {
+    let hyper_err = err.source().and_then(|e| e.downcast_ref::<hyper::Error>());
+    let h2_err = hyper_err.and_then(|e| e.source()).and_then(|e| e.downcast_ref::<h2::Error>());
+    h2_err
+        .map(|e| e.to_string().contains("not a result of an error"))
+        .unwrap_or(false)
+}
+
+async fn sleep_with_jitter(delay: Duration) {
+    let jitter = rand::thread_rng().gen_range(0.5..1.0);
+    tokio::time::sleep(delay.mul_f64(jitter)).await;
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use csv::ReaderBuilder;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use rusqlite::Connection;
+
+use crate::find_existing_logo;
+use crate::sink::OutputFormat;
+
+/// A single scraped row, keyed by its original column name, independent of
+/// whether it came from CSV, JSONL, or SQLite.
+type Row = HashMap<String, String>;
+
+/// Serve `out_dir`'s logos and `table_path`'s rows (in `format`) as a
+/// browsable HTML index at `addr`, so a scrape can be QA'd from a browser
+/// instead of the shell.
+pub async fn serve(addr: SocketAddr, format: OutputFormat, table_path: &str, out_dir: &str) -> Result<()> {
+    let table_path = table_path.to_string();
+    let out_dir = out_dir.to_string();
+    println!("Serving {out_dir} and {table_path} at http://{addr}/");
+
+    let make_svc = make_service_fn(move |_conn| {
+        let table_path = table_path.clone();
+        let out_dir = out_dir.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(req, format, table_path.clone(), out_dir.clone()))) }
+    });
+
+    Server::bind(&addr).serve(make_svc).await.context("http server")?;
+    Ok(())
+}
+
+async fn handle(
+    req: Request<Body>,
+    format: OutputFormat,
+    table_path: String,
+    out_dir: String,
+) -> Result<Response<Body>, Infallible> {
+    let response = match req.uri().path() {
+        "/" | "/index.html" => render_index(format, &table_path, &out_dir).unwrap_or_else(error_response),
+        path => match path.strip_prefix("/logos/").filter(|name| is_safe_logo_name(name)) {
+            Some(name) => serve_logo(Path::new(&out_dir).join(name)).unwrap_or_else(error_response),
+            None => not_found(),
+        },
+    };
+    Ok(response)
+}
+
+/// Reject anything but a single, plain filename: no path separators, no
+/// `..`, and nothing absolute, so `/logos/<name>` can't escape `out_dir`.
+fn is_safe_logo_name(name: &str) -> bool {
+    !name.is_empty() && Path::new(name).components().count() == 1 && Path::new(name).file_name().is_some()
+}
+
+fn serve_logo(path: impl AsRef<Path>) -> Result<Response<Body>> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let content_type = match path.extension().and_then(|e| e.to_str()) {
+        Some("png") => "image/png",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        _ => "application/octet-stream",
+    };
+    Ok(Response::builder()
+        .header("Content-Type", content_type)
+        .body(Body::from(bytes))
+        .unwrap())
+}
+
+fn not_found() -> Response<Body> {
+    Response::builder().status(StatusCode::NOT_FOUND).body(Body::from("not found")).unwrap()
+}
+
+fn error_response(err: anyhow::Error) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::INTERNAL_SERVER_ERROR)
+        .body(Body::from(err.to_string()))
+        .unwrap()
+}
+
+/// Escape text pulled from the scraped table before interpolating it into
+/// HTML; airline names come from a public wiki page and aren't trusted.
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Read `path` (in `format`) into rows keyed by their original column name,
+/// so `render_index` doesn't need to know the on-disk representation.
+fn read_rows(format: OutputFormat, path: &str) -> Result<Vec<Row>> {
+    match format {
+        OutputFormat::Csv => read_csv_rows(path),
+        OutputFormat::Jsonl => read_jsonl_rows(path),
+        OutputFormat::Sqlite => read_sqlite_rows(path),
+    }
+}
+
+fn read_csv_rows(path: &str) -> Result<Vec<Row>> {
+    let mut rdr = ReaderBuilder::new().has_headers(true).flexible(true).from_path(path)?;
+    let headers = rdr.headers()?.clone();
+    let mut rows = Vec::new();
+    for rec in rdr.records() {
+        let rec = rec?;
+        let row = headers.iter().zip(rec.iter()).map(|(h, v)| (h.to_string(), v.to_string())).collect();
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn read_jsonl_rows(path: &str) -> Result<Vec<Row>> {
+    let text = fs::read_to_string(path).with_context(|| format!("reading {path}"))?;
+    let mut rows = Vec::new();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let value: serde_json::Value = serde_json::from_str(line).with_context(|| format!("parsing line in {path}"))?;
+        let serde_json::Value::Object(map) = value else { continue };
+        let row = map
+            .into_iter()
+            .map(|(k, v)| (k, v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())))
+            .collect();
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn read_sqlite_rows(path: &str) -> Result<Vec<Row>> {
+    let conn = Connection::open(path).with_context(|| format!("opening {path}"))?;
+    let mut stmt = conn.prepare("SELECT * FROM airlines")?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let rows = stmt
+        .query_map([], |r| {
+            let row = columns
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.clone(), r.get::<_, Option<String>>(i).unwrap_or_default().unwrap_or_default()))
+                .collect();
+            Ok(row)
+        })?
+        .collect::<rusqlite::Result<Vec<Row>>>()?;
+    Ok(rows)
+}
+
+/// Case-insensitively find a column whose name satisfies `pred`.
+fn find_value(row: &Row, pred: impl Fn(&str) -> bool) -> Option<&str> {
+    row.iter().find(|(k, _)| pred(&k.to_ascii_lowercase())).map(|(_, v)| v.as_str())
+}
+
+/// Render the directory index: one row per airline, with IATA code, name,
+/// an inline logo thumbnail, and the logo file's size/modified time.
+fn render_index(format: OutputFormat, table_path: &str, out_dir: &str) -> Result<Response<Body>> {
+    let rows = read_rows(format, table_path)?;
+
+    let out_dir_path = Path::new(out_dir);
+    let mut rows_html = String::new();
+    for row in &rows {
+        let Some(iata) = find_value(row, |k| k == "iata").map(|s| s.trim().to_uppercase()) else { continue };
+        if iata.len() != 2 || !iata.chars().all(|c| c.is_ascii_alphanumeric()) {
+            continue;
+        }
+        let name = escape_html(find_value(row, |k| k.contains("airline")).unwrap_or(""));
+
+        let (img_cell, size_cell, modified_cell) = match find_existing_logo(out_dir_path, &iata) {
+            Some(path) => {
+                let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+                let meta = fs::metadata(&path).ok();
+                let size = meta.as_ref().map(|m| m.len()).unwrap_or(0);
+                let modified = meta
+                    .and_then(|m| m.modified().ok())
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                (format!("<img src=\"/logos/{file_name}\" height=\"32\">"), size.to_string(), modified.to_string())
+            }
+            None => (String::new(), String::new(), String::new()),
+        };
+
+        rows_html.push_str(&format!(
+            "<tr><td>{iata}</td><td>{name}</td><td>{img_cell}</td><td data-sort=\"{size_cell}\">{size_cell}</td><td data-sort=\"{modified_cell}\">{modified_cell}</td></tr>\n"
+        ));
+    }
+
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><title>iata-scraper index</title>
+<style>table {{ border-collapse: collapse; }} th, td {{ border: 1px solid #ccc; padding: 4px 8px; }} th {{ cursor: pointer; background: #eee; }}</style>
+</head>
+<body>
+<h1>Scraped airlines</h1>
+<table id="airlines">
+<thead><tr><th>IATA</th><th>Name</th><th>Logo</th><th>Size (bytes)</th><th>Modified (unix)</th></tr></thead>
+<tbody>
+{rows_html}</tbody>
+</table>
+<script>
+document.querySelectorAll('#airlines th').forEach((th, col) => {{
+  th.addEventListener('click', () => {{
+    const tbody = document.querySelector('#airlines tbody');
+    const rows = Array.from(tbody.querySelectorAll('tr'));
+    rows.sort((a, b) => {{
+      const ca = a.children[col], cb = b.children[col];
+      const va = ca.dataset.sort ?? ca.textContent;
+      const vb = cb.dataset.sort ?? cb.textContent;
+      return va.localeCompare(vb, undefined, {{ numeric: true }});
+    }});
+    rows.forEach(r => tbody.appendChild(r));
+  }});
+}});
+</script>
+</body>
+</html>"#
+    );
+
+    Ok(Response::new(Body::from(html)))
+}
@@ -0,0 +1,100 @@
+use std::fs::File;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use csv::WriterBuilder;
+
+mod jsonl;
+mod sqlite;
+
+pub use jsonl::JsonlSink;
+pub use sqlite::SqliteSink;
+
+/// Selectable output backend for the normalized airline table.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OutputFormat {
+    Csv,
+    Jsonl,
+    Sqlite,
+}
+
+impl OutputFormat {
+    /// Default filename extension for this format, used when `--output` isn't given.
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Jsonl => "jsonl",
+            OutputFormat::Sqlite => "sqlite",
+        }
+    }
+}
+
+/// A sink for normalized airline rows (every row already padded/truncated
+/// to the header's width). Implementations own whatever serialization
+/// state they need; `finish` flushes and closes it.
+pub trait RowSink {
+    fn write_header(&mut self, header: &[String]) -> Result<()>;
+    fn write_row(&mut self, row: &[String]) -> Result<()>;
+    fn finish(self: Box<Self>) -> Result<()>;
+}
+
+pub struct CsvSink {
+    writer: csv::Writer<File>,
+}
+
+impl CsvSink {
+    pub fn create(path: &str) -> Result<Self> {
+        Ok(Self {
+            writer: WriterBuilder::new().has_headers(true).from_path(path)?,
+        })
+    }
+}
+
+impl RowSink for CsvSink {
+    fn write_header(&mut self, header: &[String]) -> Result<()> {
+        self.writer.write_record(header)?;
+        Ok(())
+    }
+
+    fn write_row(&mut self, row: &[String]) -> Result<()> {
+        self.writer.write_record(row)?;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Build the sink selected by `format`, writing to `path`.
+pub fn create_sink(format: OutputFormat, path: &str) -> Result<Box<dyn RowSink>> {
+    match format {
+        OutputFormat::Csv => Ok(Box::new(CsvSink::create(path)?)),
+        OutputFormat::Jsonl => Ok(Box::new(JsonlSink::create(path)?)),
+        OutputFormat::Sqlite => Ok(Box::new(SqliteSink::create(path)?)),
+    }
+}
+
+/// Normalize every row to the header width (padding short rows, truncating
+/// long ones) to avoid raggedness, then hand each to `sink`.
+pub fn write_rows(mut sink: Box<dyn RowSink>, header: &[String], rows: &[Vec<String>]) -> Result<()> {
+    sink.write_header(header)?;
+    let hlen = header.len();
+    for r in rows {
+        sink.write_row(&normalize_row(r, hlen))?;
+    }
+    sink.finish()
+}
+
+fn normalize_row(r: &[String], hlen: usize) -> Vec<String> {
+    if r.len() == hlen {
+        r.to_vec()
+    } else if r.len() > hlen {
+        r[..hlen].to_vec()
+    } else {
+        let mut tmp = r.to_vec();
+        tmp.resize(hlen, String::new());
+        tmp
+    }
+}
@@ -0,0 +1,42 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use anyhow::Result;
+use serde_json::Value;
+
+use super::RowSink;
+
+/// Writes one JSON object per line, keyed by the header names.
+pub struct JsonlSink {
+    writer: BufWriter<File>,
+    header: Vec<String>,
+}
+
+impl JsonlSink {
+    pub fn create(path: &str) -> Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            header: Vec::new(),
+        })
+    }
+}
+
+impl RowSink for JsonlSink {
+    fn write_header(&mut self, header: &[String]) -> Result<()> {
+        self.header = header.to_vec();
+        Ok(())
+    }
+
+    fn write_row(&mut self, row: &[String]) -> Result<()> {
+        let obj: serde_json::Map<String, Value> =
+            self.header.iter().cloned().zip(row.iter().map(|v| Value::String(v.clone()))).collect();
+        serde_json::to_writer(&mut self.writer, &Value::Object(obj))?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
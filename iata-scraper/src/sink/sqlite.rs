@@ -0,0 +1,55 @@
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+use super::RowSink;
+
+/// Writes rows into a fresh `airlines` table keyed on IATA, with one TEXT
+/// column per header entry, enabling indexed lookups and joins downstream.
+pub struct SqliteSink {
+    conn: Connection,
+    columns: Vec<String>,
+}
+
+impl SqliteSink {
+    pub fn create(path: &str) -> Result<Self> {
+        let _ = std::fs::remove_file(path);
+        let conn = Connection::open(path).with_context(|| format!("opening {path}"))?;
+        Ok(Self { conn, columns: Vec::new() })
+    }
+}
+
+impl RowSink for SqliteSink {
+    fn write_header(&mut self, header: &[String]) -> Result<()> {
+        self.columns = header.iter().map(|h| sanitize_column(h)).collect();
+
+        let cols_sql = self.columns.iter().map(|c| format!("\"{c}\" TEXT")).collect::<Vec<_>>().join(", ");
+        self.conn.execute(&format!("CREATE TABLE airlines ({cols_sql})"), [])?;
+
+        if let Some(iata_col) = self.columns.iter().find(|c| c.eq_ignore_ascii_case("iata")) {
+            self.conn.execute(&format!("CREATE INDEX idx_airlines_iata ON airlines(\"{iata_col}\")"), [])?;
+        }
+        Ok(())
+    }
+
+    fn write_row(&mut self, row: &[String]) -> Result<()> {
+        let cols_sql = self.columns.iter().map(|c| format!("\"{c}\"")).collect::<Vec<_>>().join(", ");
+        let placeholders = vec!["?"; row.len()].join(", ");
+        let sql = format!("INSERT INTO airlines ({cols_sql}) VALUES ({placeholders})");
+        self.conn.execute(&sql, rusqlite::params_from_iter(row))?;
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// SQLite column names must be simple identifiers; map header text to one.
+fn sanitize_column(header: &str) -> String {
+    let mut col: String =
+        header.trim().chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    if col.is_empty() || col.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+        col = format!("col_{col}");
+    }
+    col
+}